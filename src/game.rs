@@ -1,39 +1,52 @@
-use std::collections::HashMap;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
 
-use itertools::Itertools;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
 
-use crate::grid::{Grid, Location, ALL_DIRECTIONS};
+use crate::grid::{all_directions, Grid, Location};
 use crate::{Outcome, Player};
 
-pub struct Game {
+#[derive(Clone)]
+pub struct Game<const N: usize = 2> {
     win_length: u32,
-    grid: Grid,
-    moves_made: HashMap<Player, u32>,
-    last_move: Option<Location>,
+    grid: Grid<N>,
+    moves_made: BTreeMap<Player, u32>,
+    last_move: Option<Location<N>>,
+    last_outcome: Option<Outcome>,
 }
 
-impl Game {
-    pub fn from_string(desc: &str) -> Result<Game, Outcome> {
-        if let Some((width, height, win_length)) = desc
+impl<const N: usize> Game<N> {
+    pub fn from_string(desc: &str) -> Result<Game<N>, Outcome> {
+        let numbers: Vec<u32> = desc
             .split_ascii_whitespace()
-            .map(|v| v.parse::<u32>().expect("a number"))
-            .collect_tuple()
-        {
-            Game::new(width, height, win_length)
-        } else {
-            Err(Outcome::InvalidFile)
+            .map(|v| v.parse::<u32>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| Outcome::InvalidFile)?;
+
+        if numbers.len() != N + 1 {
+            return Err(Outcome::InvalidFile);
         }
+
+        let mut dims = [0u32; N];
+        dims.copy_from_slice(&numbers[..N]);
+        Game::new(dims, numbers[N])
     }
 
-    pub fn new(width: u32, height: u32, win_length: u32) -> Result<Game, Outcome> {
-        if win_length > width && win_length > height {
+    pub fn new(dims: [u32; N], win_length: u32) -> Result<Game<N>, Outcome> {
+        if dims.iter().all(|&dim| win_length > dim) {
             Err(Outcome::IllegalGame)
         } else {
+            let mut sizes = [0usize; N];
+            for (size, &dim) in sizes.iter_mut().zip(dims.iter()) {
+                *size = dim as usize;
+            }
             Ok(Game {
                 win_length,
-                grid: Grid::with_dimensions(width as usize, height as usize),
-                moves_made: HashMap::new(),
+                grid: Grid::with_dimensions(sizes),
+                moves_made: BTreeMap::new(),
                 last_move: None,
+                last_outcome: None,
             })
         }
     }
@@ -63,7 +76,163 @@ impl Game {
         Outcome::Incomplete
     }
 
-    fn make_move(&mut self, player: Player, column: u32) -> Option<Outcome> {
+    /// The player whose turn it is to move. Player 1 always moves first, so
+    /// the parity of the number of moves played determines the mover.
+    pub fn current_player(&self) -> Player {
+        if self.moves_played() % 2 == 0 {
+            1
+        } else {
+            2
+        }
+    }
+
+    fn moves_played(&self) -> u32 {
+        self.moves_made.values().sum()
+    }
+
+    /// Return the best legal column for the player to move and its negamax
+    /// score. Wins are scored `(cells + 1 - moves_played) / 2` so that a
+    /// quicker win scores higher and a quicker loss lower; draws score `0`.
+    pub fn solve(&self) -> (Option<u32>, i32) {
+        let mut table = BTreeMap::new();
+        let player = self.current_player();
+
+        let mut best_column = None;
+        let mut best_score = i32::MIN + 1;
+
+        for column in self.column_order() {
+            let mut next = self.clone();
+            let score = match next.make_move(player, column) {
+                Some(Outcome::IllegalColumn) | Some(Outcome::IllegalRow) => continue,
+                terminal => next.score_after(terminal, i32::MIN + 1, i32::MAX - 1, &mut table),
+            };
+            if score > best_score {
+                best_score = score;
+                best_column = Some(column);
+            }
+        }
+
+        (best_column, best_score)
+    }
+
+    fn negamax(
+        &self,
+        mut alpha: i32,
+        mut beta: i32,
+        table: &mut BTreeMap<Vec<u8>, (i32, i32)>,
+    ) -> i32 {
+        if self.grid.is_full() {
+            return 0;
+        }
+
+        let key = self.grid.serialize();
+        let original_alpha = alpha;
+        if let Some(&(lower, upper)) = table.get(&key) {
+            if lower >= beta {
+                return lower;
+            }
+            if upper <= alpha {
+                return upper;
+            }
+            alpha = alpha.max(lower);
+            beta = beta.min(upper);
+        }
+
+        let player = self.current_player();
+        let mut value = i32::MIN + 1;
+        for column in self.column_order() {
+            let mut next = self.clone();
+            let score = match next.make_move(player, column) {
+                Some(Outcome::IllegalColumn) | Some(Outcome::IllegalRow) => continue,
+                terminal => next.score_after(terminal, alpha, beta, table),
+            };
+            value = value.max(score);
+            alpha = alpha.max(value);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bounds = if value <= original_alpha {
+            (i32::MIN + 1, value)
+        } else if value >= beta {
+            (value, i32::MAX - 1)
+        } else {
+            (value, value)
+        };
+        table.insert(key, bounds);
+
+        value
+    }
+
+    /// Score `self` (a position reached by `make_move` returning `outcome`)
+    /// from the moving player's perspective, recursing through `negamax` for
+    /// non-terminal positions.
+    fn score_after(
+        &self,
+        outcome: Option<Outcome>,
+        alpha: i32,
+        beta: i32,
+        table: &mut BTreeMap<Vec<u8>, (i32, i32)>,
+    ) -> i32 {
+        match outcome {
+            Some(Outcome::PlayerWin(_)) => {
+                (self.grid.cells() as i32 + 1 - self.moves_played() as i32) / 2
+            }
+            Some(Outcome::Draw) => 0,
+            _ => -self.negamax(-beta, -alpha, table),
+        }
+    }
+
+    /// Columns ordered by the distance of their flat index from the middle
+    /// index. On a 2D board this is centre outwards, which improves alpha-beta
+    /// pruning because strong moves tend to cluster in the middle; on boards of
+    /// three or more dimensions the order follows the flattened index and does
+    /// not start from the geometric centre.
+    fn column_order(&self) -> Vec<u32> {
+        let count = self.grid.num_columns();
+        let centre = count as i64 / 2;
+        let mut columns: Vec<u32> = (0..count as u32).collect();
+        columns.sort_by_key(|&c| (c as i64 - centre).abs());
+        columns
+    }
+
+    /// Apply a single move for `player` in `column`, returning a terminal
+    /// `Outcome` if the move ends the game (win, illegal move, or draw) or
+    /// `None` if play may continue. The outcome is recorded so it can be
+    /// inspected later via `last_outcome`.
+    pub fn make_move(&mut self, player: Player, column: u32) -> Option<Outcome> {
+        let outcome = self.apply(player, column);
+        self.last_outcome = outcome.clone();
+        outcome
+    }
+
+    /// The outcome of the most recent move, if one has been made.
+    pub fn last_outcome(&self) -> Option<&Outcome> {
+        self.last_outcome.as_ref()
+    }
+
+    /// Record a terminal outcome that was determined outside `make_move`,
+    /// such as `push` rejecting column `0`, so it surfaces through
+    /// `last_outcome`.
+    pub fn record_outcome(&mut self, outcome: Outcome) -> Outcome {
+        self.last_outcome = Some(outcome.clone());
+        outcome
+    }
+
+    /// Drop a piece for the player to move into a 1-indexed `column`. Column
+    /// `0` is invalid input and yields `IllegalColumn` rather than underflowing
+    /// into the last column. This is the single entry point every 1-indexed
+    /// caller (CLI, streaming, Python) shares.
+    pub fn push(&mut self, column: u32) -> Option<Outcome> {
+        if column == 0 {
+            return Some(self.record_outcome(Outcome::IllegalColumn));
+        }
+        let player = self.current_player();
+        self.make_move(player, column - 1)
+    }
+
+    fn apply(&mut self, player: Player, column: u32) -> Option<Outcome> {
         match self.grid.insert_piece(player, column) {
             Ok(location) => {
                 self.last_move = Some(location);
@@ -76,8 +245,8 @@ impl Game {
         };
 
         if self.could_win(player) {
-            for direction in ALL_DIRECTIONS.iter() {
-                let streak = self.grid.get_streak(self.last_move.expect(""), *direction);
+            for direction in all_directions::<N>() {
+                let streak = self.grid.get_streak(self.last_move.expect(""), direction);
                 if streak >= self.win_length {
                     return Some(Outcome::PlayerWin(player));
                 }
@@ -99,3 +268,44 @@ impl Game {
         }
     }
 }
+
+/// Concrete, non-generic wrapper exposed to Python. `#[pyclass]` cannot be
+/// placed on the const-generic `Game<N>`, so the bindings drive a fixed 2D
+/// game through this newtype.
+#[cfg(feature = "python")]
+#[pyclass(name = "Game")]
+pub struct PyGame(Game<2>);
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl PyGame {
+    #[new]
+    fn py_new(header: &str) -> PyResult<PyGame> {
+        Game::from_string(header)
+            .map(PyGame)
+            .map_err(|outcome| pyo3::exceptions::PyValueError::new_err(format!("{}", outcome)))
+    }
+
+    /// Drop a piece for the player to move into `column` (1-indexed), returning
+    /// the terminal outcome code if the move ends the game.
+    #[pyo3(name = "push")]
+    fn py_push(&mut self, column: u32) -> Option<u8> {
+        self.0.push(column).map(|o| *o.as_u8())
+    }
+
+    /// The outcome code of the most recent move, if any.
+    #[pyo3(name = "outcome")]
+    fn py_outcome(&self) -> Option<u8> {
+        self.0.last_outcome().map(|o| *o.as_u8())
+    }
+
+    #[pyo3(name = "is_full")]
+    fn py_is_full(&self) -> bool {
+        self.0.grid.is_full()
+    }
+
+    #[pyo3(name = "current_player")]
+    fn py_current_player(&self) -> u8 {
+        self.0.current_player()
+    }
+}
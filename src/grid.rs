@@ -1,95 +1,157 @@
-use std::ops;
+use core::ops;
+
+use alloc::vec;
+use alloc::vec::Vec;
 
 use crate::{Outcome, Player};
 
-#[derive(PartialEq, Clone, Copy, Debug)]
-pub struct Direction(i8, i8);
-
-const HORIZONTAL: Direction = Direction(1, 0);
-const VERTICAL: Direction = Direction(0, 1);
-const FORWARD_DIAGONAL: Direction = Direction(1, 1);
-const BACKWARD_DIAGONAL: Direction = Direction(-1, 1);
-
-pub const ALL_DIRECTIONS: [Direction; 4] =
-    [HORIZONTAL, VERTICAL, FORWARD_DIAGONAL, BACKWARD_DIAGONAL];
-
-#[derive(PartialEq, Clone, Copy, Debug)]
-pub struct Location(u32, u32);
-
-impl ops::Add<Direction> for Location {
-    type Output = Result<Location, &'static str>;
-
-    fn add(self, rhs: Direction) -> Self::Output {
-        if self.0 == 0 && rhs.0 < 0 {
-            return Err("already at first column");
-        } else if self.1 == 0 && rhs.1 < 0 {
-            return Err("already at first row");
-        } else {
-            Ok(Location(
-                (self.0 as i64 + rhs.0 as i64) as u32,
-                (self.1 as i64 + rhs.1 as i64) as u32,
-            ))
+/// An `N`-dimensional integer vector, used both as an absolute board
+/// coordinate (`Location`) and as a relative step (`Direction`).
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct VecN<const N: usize>([i64; N]);
+
+/// A position on the board.
+pub type Location<const N: usize> = VecN<N>;
+/// A unit step between positions along a single line.
+pub type Direction<const N: usize> = VecN<N>;
+
+impl<const N: usize> VecN<N> {
+    pub fn new(values: [i64; N]) -> VecN<N> {
+        VecN(values)
+    }
+}
+
+/// Every distinct line direction in `N` dimensions.
+///
+/// Enumerates every vector in `{-1, 0, 1}^N`, drops the all-zero vector, and
+/// keeps only one of each `{v, -v}` pair by requiring the first non-zero
+/// component to be positive. This yields `(3^N - 1) / 2` directions (4 for
+/// `N = 2`, 13 for `N = 3`) so a line is never counted alongside its reverse.
+pub fn all_directions<const N: usize>() -> Vec<Direction<N>> {
+    let mut directions = Vec::new();
+    let total = 3usize.pow(N as u32);
+
+    for i in 0..total {
+        let mut components = [0i64; N];
+        let mut remainder = i;
+        for component in components.iter_mut() {
+            *component = (remainder % 3) as i64 - 1;
+            remainder /= 3;
+        }
+
+        match components.iter().find(|&&c| c != 0) {
+            Some(&first) if first > 0 => directions.push(VecN(components)),
+            _ => (),
+        }
+    }
+
+    directions
+}
+
+impl<const N: usize> ops::Add for VecN<N> {
+    type Output = Result<VecN<N>, &'static str>;
+
+    fn add(self, rhs: VecN<N>) -> Self::Output {
+        let mut values = [0i64; N];
+        for axis in 0..N {
+            let value = self.0[axis] + rhs.0[axis];
+            if value < 0 {
+                return Err("already at first position on axis");
+            }
+            values[axis] = value;
         }
+        Ok(VecN(values))
     }
 }
 
-impl ops::Sub<Direction> for Location {
-    type Output = Result<Location, &'static str>;
-
-    fn sub(self, rhs: Direction) -> Self::Output {
-        if self.0 == 0 && rhs.0 > 0 {
-            return Err("already at first column");
-        } else if self.1 == 0 && rhs.1 > 0 {
-            return Err("already at first row");
-        } else {
-            Ok(Location(
-                (self.0 as i64 - rhs.0 as i64) as u32,
-                (self.1 as i64 - rhs.1 as i64) as u32,
-            ))
+impl<const N: usize> ops::Sub for VecN<N> {
+    type Output = Result<VecN<N>, &'static str>;
+
+    fn sub(self, rhs: VecN<N>) -> Self::Output {
+        let mut values = [0i64; N];
+        for axis in 0..N {
+            let value = self.0[axis] - rhs.0[axis];
+            if value < 0 {
+                return Err("already at first position on axis");
+            }
+            values[axis] = value;
         }
+        Ok(VecN(values))
     }
 }
 
-pub struct Grid {
-    values: Vec<Vec<Player>>,
-    max_height: usize,
+#[derive(Clone)]
+pub struct Grid<const N: usize> {
+    dims: [usize; N],
+    columns: Vec<Vec<Player>>,
 }
 
-impl Grid {
-    pub fn with_dimensions(width: usize, height: usize) -> Grid {
+impl<const N: usize> Grid<N> {
+    /// Build an empty grid. The final axis is the gravity axis (pieces stack
+    /// along it); the remaining axes address a column.
+    pub fn with_dimensions(dims: [usize; N]) -> Grid<N> {
+        let count = dims[..N - 1].iter().product::<usize>().max(1);
+        let height = dims[N - 1];
         Grid {
-            values: vec![Vec::with_capacity(height); width],
-            max_height: height,
+            dims,
+            columns: vec![Vec::with_capacity(height); count],
         }
     }
 
-    pub fn at(&self, loc: Location) -> Option<&Player> {
-        if let Some(col) = self.values.get(loc.0 as usize) {
-            col.get(loc.1 as usize)
-        } else {
-            None
+    pub fn at(&self, loc: Location<N>) -> Option<&Player> {
+        let height = loc.0[N - 1];
+        if height < 0 {
+            return None;
         }
+        let column = self.column_index(&loc.0)?;
+        self.columns.get(column)?.get(height as usize)
     }
 
     pub fn is_full(&self) -> bool {
-        self.values.iter().all(|col| col.len() == self.max_height)
+        self.columns.iter().all(|col| col.len() == self.dims[N - 1])
     }
 
-    pub fn insert_piece(&mut self, player: Player, column: u32) -> Result<Location, Outcome> {
-        let col = match self.values.get_mut(column as usize) {
+    /// The number of columns (the product of the non-gravity axes).
+    pub fn num_columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// The total number of cells the board can hold.
+    pub fn cells(&self) -> usize {
+        self.columns.len() * self.dims[N - 1]
+    }
+
+    /// A compact, order-preserving encoding of the board used as a
+    /// transposition-table key. Empty slots are `0`; filled slots carry the
+    /// owning player, with every column padded to the full height.
+    pub fn serialize(&self) -> Vec<u8> {
+        let height = self.dims[N - 1];
+        let mut state = Vec::with_capacity(self.columns.len() * height);
+        for col in &self.columns {
+            for slot in 0..height {
+                state.push(col.get(slot).copied().unwrap_or(0));
+            }
+        }
+        state
+    }
+
+    pub fn insert_piece(&mut self, player: Player, column: u32) -> Result<Location<N>, Outcome> {
+        let col = match self.columns.get_mut(column as usize) {
             Some(col) => col,
             None => return Err(Outcome::IllegalColumn),
         };
-        let length = col.len();
-        if length >= self.max_height {
+        let height = col.len();
+        if height >= self.dims[N - 1] {
             return Err(Outcome::IllegalRow);
         }
         col.push(player);
 
-        Ok(Location(column, length as u32))
+        let mut coords = self.decode_column(column as usize);
+        coords[N - 1] = height as i64;
+        Ok(VecN(coords))
     }
 
-    pub fn get_streak(&self, start: Location, direction: Direction) -> u32 {
+    pub fn get_streak(&self, start: Location<N>, direction: Direction<N>) -> u32 {
         let player = match self.at(start) {
             Some(player) => player,
             None => return 0,
@@ -126,6 +188,32 @@ impl Grid {
 
         streak
     }
+
+    /// Flatten the non-gravity axes of a coordinate into a column index,
+    /// or `None` if any axis is out of bounds.
+    fn column_index(&self, coords: &[i64; N]) -> Option<usize> {
+        let mut index = 0usize;
+        for axis in 0..N - 1 {
+            let value = coords[axis];
+            if value < 0 || value as usize >= self.dims[axis] {
+                return None;
+            }
+            index = index * self.dims[axis] + value as usize;
+        }
+        Some(index)
+    }
+
+    /// Inverse of `column_index`: recover the non-gravity axes from a flat
+    /// column index. The gravity axis is left at zero for the caller to set.
+    fn decode_column(&self, column: usize) -> [i64; N] {
+        let mut coords = [0i64; N];
+        let mut remainder = column;
+        for axis in (0..N - 1).rev() {
+            coords[axis] = (remainder % self.dims[axis]) as i64;
+            remainder /= self.dims[axis];
+        }
+        coords
+    }
 }
 
 #[cfg(test)]
@@ -134,55 +222,49 @@ mod tests {
 
     #[test]
     fn add_direction() {
-        let location = Location(1, 2);
-        let direction = Direction(1, -1);
+        let location = VecN([1, 2]);
+        let direction = VecN([1, 1]);
 
-        assert_eq!(location + direction, Ok(Location(2, 1)));
+        assert_eq!(location + direction, Ok(VecN([2, 3])));
     }
 
     #[test]
-    fn add_direction_column_error() {
-        let location = Location(0, 0);
-        let direction = Direction(-1, 0);
+    fn add_direction_error() {
+        let location = VecN([0, 0]);
+        let direction = VecN([-1, 0]);
 
-        assert_eq!(location + direction, Err("already at first column"));
+        assert_eq!(location + direction, Err("already at first position on axis"));
     }
 
     #[test]
-    fn add_direction_row_error() {
-        let location = Location(0, 0);
-        let direction = Direction(0, -1);
+    fn sub_direction() {
+        let location = VecN([1, 2]);
+        let direction = VecN([1, 1]);
 
-        assert_eq!(location + direction, Err("already at first row"));
+        assert_eq!(location - direction, Ok(VecN([0, 1])));
     }
 
     #[test]
-    fn sub_direction() {
-        let location = Location(1, 2);
-        let direction = Direction(1, -1);
+    fn sub_direction_error() {
+        let location = VecN([0, 0]);
+        let direction = VecN([1, 0]);
 
-        assert_eq!(location - direction, Ok(Location(0, 3)));
+        assert_eq!(location - direction, Err("already at first position on axis"));
     }
 
     #[test]
-    fn sub_direction_column_error() {
-        let location = Location(0, 0);
-        let direction = Direction(1, 0);
-
-        assert_eq!(location - direction, Err("already at first column"));
+    fn all_directions_2d() {
+        assert_eq!(all_directions::<2>().len(), 4);
     }
 
     #[test]
-    fn sub_direction_row_error() {
-        let location = Location(0, 0);
-        let direction = Direction(0, 1);
-
-        assert_eq!(location - direction, Err("already at first row"));
+    fn all_directions_3d() {
+        assert_eq!(all_directions::<3>().len(), 13);
     }
 
     #[test]
     fn grid_full() {
-        let mut grid = Grid::with_dimensions(2, 2);
+        let mut grid = Grid::with_dimensions([2, 2]);
 
         assert!(grid.insert_piece(1, 0).is_ok());
         assert!(grid.insert_piece(1, 0).is_ok());
@@ -194,14 +276,14 @@ mod tests {
 
     #[test]
     fn inserting_bad_column() {
-        let mut grid = Grid::with_dimensions(2, 2);
+        let mut grid = Grid::with_dimensions([2, 2]);
         let result = grid.insert_piece(1, 23).err();
         assert_eq!(result, Some(Outcome::IllegalColumn));
     }
 
     #[test]
     fn inserting_bad_row() {
-        let mut grid = Grid::with_dimensions(2, 2);
+        let mut grid = Grid::with_dimensions([2, 2]);
 
         assert!(grid.insert_piece(1, 0).is_ok());
         assert!(grid.insert_piece(1, 0).is_ok());
@@ -209,4 +291,17 @@ mod tests {
         let result = grid.insert_piece(1, 0).err();
         assert_eq!(result, Some(Outcome::IllegalRow));
     }
+
+    #[test]
+    fn streak_3d() {
+        let mut grid = Grid::with_dimensions([2, 2, 3]);
+
+        // Stack three of player 1 in the same column: a vertical streak along
+        // the gravity axis.
+        assert!(grid.insert_piece(1, 0).is_ok());
+        assert!(grid.insert_piece(1, 0).is_ok());
+        let top = grid.insert_piece(1, 0).unwrap();
+
+        assert_eq!(grid.get_streak(top, VecN([0, 0, 1])), 3);
+    }
 }
@@ -1,16 +1,31 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use core::fmt;
+
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+#[cfg(feature = "python")]
 use pyo3::wrap_pyfunction;
-use std::fmt;
+
+#[cfg(feature = "std")]
+use std::env;
+#[cfg(feature = "std")]
 use std::fs::File;
-use std::io::{BufRead, BufReader, Error, ErrorKind};
+#[cfg(feature = "std")]
+use std::io::{BufRead, BufReader, Error};
+#[cfg(feature = "std")]
 use std::num::ParseIntError;
-use std::{env, error};
+#[cfg(feature = "std")]
+use std::error;
 
 use game::Game;
 
 mod game;
 mod grid;
 
+#[cfg(feature = "std")]
 #[derive(Debug)]
 pub enum ConnectzError {
     Incomplete,
@@ -23,6 +38,7 @@ pub enum ConnectzError {
     Argument(String),
 }
 
+#[cfg(feature = "std")]
 impl fmt::Display for ConnectzError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -38,6 +54,7 @@ impl fmt::Display for ConnectzError {
     }
 }
 
+#[cfg(feature = "std")]
 impl error::Error for ConnectzError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         None
@@ -47,21 +64,24 @@ impl error::Error for ConnectzError {
 // Implement the conversion from `ParseIntError` to `DoubleError`.
 // This will be automatically called by `?` if a `ParseIntError`
 // needs to be converted into a `DoubleError`.
+#[cfg(feature = "std")]
 impl From<ParseIntError> for ConnectzError {
     fn from(_err: ParseIntError) -> ConnectzError {
         ConnectzError::InvalidFile
     }
 }
 
+#[cfg(feature = "std")]
 impl From<std::io::Error> for ConnectzError {
     fn from(_: Error) -> Self {
         ConnectzError::FileNotFound
     }
 }
 
+#[cfg(feature = "std")]
 type Result<T> = std::result::Result<T, ConnectzError>;
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Clone, Debug)]
 pub enum Outcome {
     Draw,
     PlayerWin(Player),
@@ -96,6 +116,7 @@ impl fmt::Display for Outcome {
     }
 }
 
+#[cfg(feature = "python")]
 impl ToPyObject for Outcome {
     fn to_object(&self, py: Python) -> PyObject {
         self.as_u8().to_object(py)
@@ -104,10 +125,12 @@ impl ToPyObject for Outcome {
 
 type Player = u8;
 
+#[cfg(feature = "std")]
 pub struct Config {
     filename: String,
 }
 
+#[cfg(feature = "std")]
 impl Config {
     pub fn new(mut args: env::Args) -> Result<Config> {
         args.next();
@@ -125,32 +148,13 @@ impl Config {
     }
 }
 
+#[cfg(feature = "std")]
 pub fn run(config: Config) -> Result<Outcome> {
     let file = File::open(config.filename)?;
-    let mut file = BufReader::new(file);
-
-    let mut header = String::new();
-    file.read_line(&mut header)?;
-
-    let mut game = Game::from_string(&header)?;
-
-    if let Ok(moves) = file
-        .lines()
-        .map(|line| {
-            line.and_then(|v| {
-                v.parse::<u32>()
-                    .map_err(|e| Error::new(ErrorKind::InvalidData, e))
-                    .map(|v| v - 1)
-            })
-        })
-        .collect()
-    {
-        Ok(game.play(&moves))
-    } else {
-        Err(ConnectzError::InvalidFile)
-    }
+    run_reader(BufReader::new(file))
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 fn run_file(filename: String) -> PyResult<String> {
     if let Ok(result) = run(Config { filename }) {
@@ -160,14 +164,154 @@ fn run_file(filename: String) -> PyResult<String> {
     }
 }
 
+/// Dispatch on the number of whitespace tokens in a header line to the
+/// matching `Game<N>` monomorphization (a header has `dims + 1` tokens, so
+/// three tokens is 2D, four is 3D, and so on). This is how an extra dimension
+/// in the header makes a runtime caller play a higher-dimensional game even
+/// though `N` is a compile-time const generic; dimensionalities beyond the
+/// listed arms evaluate to `$invalid`.
+#[cfg(any(feature = "std", feature = "python"))]
+macro_rules! with_dims {
+    ($tokens:expr, $n:ident => $body:expr, $invalid:expr $(,)?) => {
+        match $tokens {
+            3 => {
+                const $n: usize = 2;
+                $body
+            }
+            4 => {
+                const $n: usize = 3;
+                $body
+            }
+            5 => {
+                const $n: usize = 4;
+                $body
+            }
+            _ => $invalid,
+        }
+    };
+}
+
+/// Apply one move per line from `lines` to a fresh `Game<N>` built from
+/// `header`, until a terminal outcome is reached or the input is exhausted,
+/// returning the populated game and the iterator positioned after the last
+/// consumed line. Blank lines are skipped.
+#[cfg(feature = "std")]
+fn play_lines<const N: usize, L>(header: &str, mut lines: L) -> Result<(Game<N>, L)>
+where
+    L: Iterator<Item = std::io::Result<String>>,
+{
+    let mut game = Game::<N>::from_string(header)?;
+
+    while let Some(line) = lines.next() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let column = line.trim().parse::<u32>()?;
+        if game.push(column).is_some() {
+            break;
+        }
+    }
+
+    Ok((game, lines))
+}
+
+/// Drive an `N`-dimensional game to completion from an already-read `header`
+/// and the remaining move `lines`, without buffering the rest of the input.
+/// A draw or illegal move returns at once; after a win, lines are read up to
+/// the next non-blank one (or the end of input) to tell a win from
+/// `IllegalContinue`.
+#[cfg(feature = "std")]
+fn stream_outcome<const N: usize, L>(header: &str, lines: L) -> Result<Outcome>
+where
+    L: Iterator<Item = std::io::Result<String>>,
+{
+    let (game, mut lines) = play_lines::<N, _>(header, lines)?;
+
+    Ok(match game.last_outcome() {
+        Some(Outcome::PlayerWin(winner)) => {
+            // A win ends the game; any further move makes it illegal.
+            let trailing = lines
+                .by_ref()
+                .any(|line| line.map(|l| !l.trim().is_empty()).unwrap_or(false));
+            if trailing {
+                Outcome::IllegalContinue
+            } else {
+                Outcome::PlayerWin(*winner)
+            }
+        }
+        Some(outcome) => outcome.clone(),
+        None => Outcome::Incomplete,
+    })
+}
+
+/// Play a game by streaming moves from `reader`, applying one move per line
+/// as it is read and stopping at the first terminal outcome, without buffering
+/// the remaining input (after a win, reading goes on to the next non-blank
+/// line to check for an illegal continuation). The header's token count selects
+/// the board dimensionality, so a 3D header plays a 3D game.
+#[cfg(feature = "std")]
+pub fn run_reader<R: BufRead>(reader: R) -> Result<Outcome> {
+    let mut lines = reader.lines();
+    let header = match lines.next() {
+        Some(line) => line?,
+        None => return Ok(Outcome::InvalidFile),
+    };
+
+    let tokens = header.split_ascii_whitespace().count();
+    with_dims!(tokens, N => stream_outcome::<N, _>(&header, lines), Ok(Outcome::InvalidFile))
+}
+
+/// Play a game by streaming moves from standard input, for piped or
+/// interactively typed moves.
+#[cfg(feature = "std")]
+pub fn run_stdin() -> Result<Outcome> {
+    let stdin = std::io::stdin();
+    run_reader(stdin.lock())
+}
+
+#[cfg(feature = "python")]
+fn solve_file(filename: String) -> Result<(Option<u32>, i32)> {
+    let file = File::open(filename)?;
+    let mut lines = BufReader::new(file).lines();
+
+    let header = match lines.next() {
+        Some(line) => line?,
+        None => return Err(ConnectzError::InvalidFile),
+    };
+
+    let tokens = header.split_ascii_whitespace().count();
+    with_dims!(
+        tokens,
+        N => {
+            let (game, _) = play_lines::<N, _>(&header, lines)?;
+            Ok(game.solve())
+        },
+        Err(ConnectzError::InvalidFile),
+    )
+}
+
+/// Recommend the best next column (1-indexed) for the position described by
+/// `filename`, or `-1` if no move is available or the file cannot be read.
+#[cfg(feature = "python")]
+#[pyfunction]
+fn best_move(filename: String) -> PyResult<i32> {
+    match solve_file(filename) {
+        Ok((Some(column), _)) => Ok(column as i32 + 1),
+        _ => Ok(-1),
+    }
+}
+
 // create_exception!(connectz, ConnectzError, PyException);
 
 /// A Python module implemented in Rust.
+#[cfg(feature = "python")]
 #[pymodule]
 fn connectz(_py: Python, m: &PyModule) -> PyResult<()> {
     // m.add("ConnectzError", py.get_type::<ConnectzError>())?;
     m.add_function(wrap_pyfunction!(run_file, m)?)?;
-    m.add_class::<Game>()?;
+    m.add_function(wrap_pyfunction!(best_move, m)?)?;
+    m.add_class::<game::PyGame>()?;
 
     Ok(())
 }
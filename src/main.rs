@@ -1,8 +1,12 @@
+#[cfg(feature = "std")]
 use std::env;
+#[cfg(feature = "std")]
 use std::process;
 
+#[cfg(feature = "std")]
 use connectz::Config;
 
+#[cfg(feature = "std")]
 fn main() {
     let config = Config::new(env::args()).unwrap_or_else(|err| {
         eprintln!("Problem parsing arguments: {}", err);
@@ -14,3 +18,9 @@ fn main() {
         Err(err) => println!("{}", err),
     }
 }
+
+// The CLI depends on `Config`/`run`, which only exist behind the `std`
+// feature. Building the crate as `no_std` (`--no-default-features`) still
+// compiles this bin target, so it needs a stub entry point to resolve.
+#[cfg(not(feature = "std"))]
+fn main() {}